@@ -5,29 +5,62 @@ use polars::prelude::*;
 use pyo3_polars::derive::polars_expr;
 use serde::Deserialize;
 
+/// Mirrors Polars' `ClosedWindow` (as used by e.g. `date_range`), so users can
+/// express half-open vs closed interval conventions with the same vocabulary.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ClosedWindow {
+    Left,
+    Right,
+    Both,
+    None,
+}
+
 #[derive(Deserialize)]
 pub struct SweepLineKwargs {
-    pub overlapping: bool,
+    pub closed: ClosedWindow,
+    #[serde(default)]
+    pub max_rooms: Option<u32>,
 }
 
-/// Core algorithm logic.
-/// We use a generic T to handle different bit-widths (32/64) of physical data.
-fn assign<T>(
+#[derive(Deserialize)]
+pub struct ConcurrencyKwargs {
+    pub closed: ClosedWindow,
+    #[serde(default)]
+    pub per_row: bool,
+}
+
+#[derive(Deserialize)]
+pub struct ClusterKwargs {
+    pub closed: ClosedWindow,
+}
+
+/// Builds the sorted `(time, event_type, row_idx)` event stream shared by every
+/// sweep below, along with which event type tag means "arrival" vs "departure".
+///
+/// At a shared timestamp, whichever event type sorts first wins the tie:
+/// - Both/Right: the interval is closed on the side touching the next arrival,
+///   so the arrival is processed first and needs a fresh room.
+/// - None/Left: the interval is open on that side, so the departure is
+///   processed first and the room is freed for reuse.
+fn build_events<T>(
     ca_start: &ChunkedArray<T>,
     ca_end: &ChunkedArray<T>,
-    overlapping: bool,
-) -> PolarsResult<Vec<u32>>
+    closed: ClosedWindow,
+    rows: impl Iterator<Item = usize>,
+) -> PolarsResult<(Vec<(T::Native, i8, usize)>, i8, i8)>
 where
     T: PolarsNumericType,
     T::Native: Ord,
 {
-    let n = ca_start.len();
-    // overlapping=False means departure at t < arrival at t (0 < 1), so room is freed first.
-    let (arrival_type, departure_type) = if overlapping { (0i8, 1i8) } else { (1i8, 0i8) };
+    let (arrival_type, departure_type) = match closed {
+        ClosedWindow::Both | ClosedWindow::Right => (0i8, 1i8),
+        ClosedWindow::None | ClosedWindow::Left => (1i8, 0i8),
+    };
 
-    let mut events = Vec::with_capacity(n * 2);
-    for (i, (s_opt, e_opt)) in ca_start.iter().zip(ca_end.iter()).enumerate() {
-        if let (Some(s), Some(e)) = (s_opt, e_opt) {
+    let mut events = Vec::new();
+    for i in rows {
+        if let (Some(s), Some(e)) = (ca_start.get(i), ca_end.get(i)) {
             if e < s {
                 return Err(PolarsError::ComputeError(
                     "End time before start time".into(),
@@ -41,28 +74,330 @@ where
     // Sort by Time, then Priority (Type), then Row Index
     events.sort_unstable();
 
-    let mut assignments = vec![0u32; n];
-    let mut free_rooms = BinaryHeap::new();
-    let mut max_id = 0u32;
+    Ok((events, arrival_type, departure_type))
+}
 
-    for (_, event_type, idx) in events {
-        if event_type == arrival_type {
-            let id = free_rooms.pop().map(|Reverse(r)| r).unwrap_or_else(|| {
-                max_id += 1;
-                max_id
-            });
-            assignments[idx] = id;
-        } else {
-            free_rooms.push(Reverse(assignments[idx]));
+/// Assigns a compact `0..g` group id to each element of an iterator, in order of
+/// first appearance, by hashing the native value directly (no formatting/allocation).
+fn group_ids_from_values<V>(values: impl Iterator<Item = V>) -> Vec<usize>
+where
+    V: std::hash::Hash + Eq,
+{
+    let mut next_id = 0usize;
+    let mut seen: std::collections::HashMap<V, usize> = std::collections::HashMap::new();
+    values
+        .map(|v| {
+            *seen.entry(v).or_insert_with(|| {
+                let id = next_id;
+                next_id += 1;
+                id
+            })
+        })
+        .collect()
+}
+
+/// Maps a partition-key Series to a compact `0..g` group id per row, assigned in
+/// order of first appearance. Dispatches on the physical type like
+/// `sweep_line_assignment` does, so grouping is a direct hash over native values rather than
+/// formatting each row into a `String`.
+fn group_ids_of(s: &Series) -> PolarsResult<Vec<usize>> {
+    let phys = s.to_physical_repr();
+    match phys.dtype() {
+        DataType::Int64 => Ok(group_ids_from_values(phys.i64()?.iter())),
+        DataType::Int32 => Ok(group_ids_from_values(phys.i32()?.iter())),
+        DataType::Int16 => Ok(group_ids_from_values(phys.i16()?.iter())),
+        DataType::Int8 => Ok(group_ids_from_values(phys.i8()?.iter())),
+        DataType::UInt64 => Ok(group_ids_from_values(phys.u64()?.iter())),
+        DataType::UInt32 => Ok(group_ids_from_values(phys.u32()?.iter())),
+        DataType::UInt16 => Ok(group_ids_from_values(phys.u16()?.iter())),
+        DataType::UInt8 => Ok(group_ids_from_values(phys.u8()?.iter())),
+        DataType::Boolean => Ok(group_ids_from_values(phys.bool()?.iter())),
+        DataType::String => Ok(group_ids_from_values(phys.str()?.iter())),
+        dt => Err(PolarsError::ComputeError(
+            format!("Unsupported partition-key physical type: {dt:?}").into(),
+        )),
+    }
+}
+
+/// Partitions row indices `0..n` by `groups[i]`, or a single partition containing
+/// every row when there's no grouping. Rows keep ascending order within each
+/// partition; the order of the partitions themselves is not defined.
+fn partitions_of(n: usize, groups: Option<&[usize]>) -> Vec<Vec<usize>> {
+    match groups {
+        None => vec![(0..n).collect()],
+        Some(group_ids) => {
+            let mut by_group: std::collections::HashMap<usize, Vec<usize>> =
+                std::collections::HashMap::new();
+            for (i, &gid) in group_ids.iter().enumerate() {
+                by_group.entry(gid).or_default().push(i);
+            }
+            by_group.into_values().collect()
+        },
+    }
+}
+
+/// Core algorithm logic.
+/// We use a generic T to handle different bit-widths (32/64) of physical data.
+///
+/// When `groups` is set, the sweep runs independently within each partition (e.g.
+/// per venue): `free_rooms` and `max_id` reset at every group boundary, so room
+/// ids are scoped to their group and always start from 1.
+fn assign<T>(
+    ca_start: &ChunkedArray<T>,
+    ca_end: &ChunkedArray<T>,
+    closed: ClosedWindow,
+    max_rooms: Option<u32>,
+    groups: Option<&[usize]>,
+) -> PolarsResult<Vec<Option<u32>>>
+where
+    T: PolarsNumericType,
+    T::Native: Ord,
+{
+    let n = ca_start.len();
+    let mut assignments: Vec<Option<u32>> = vec![None; n];
+
+    for rows in partitions_of(n, groups) {
+        let (events, arrival_type, _departure_type) =
+            build_events(ca_start, ca_end, closed, rows.into_iter())?;
+
+        let mut free_rooms = BinaryHeap::new();
+        let mut max_id = 0u32;
+
+        for (_, event_type, idx) in events {
+            if event_type == arrival_type {
+                let id = free_rooms.pop().map(|Reverse(r)| r).or_else(|| {
+                    if max_rooms.is_some_and(|cap| max_id >= cap) {
+                        // At capacity and nothing free: this interval gets no room.
+                        None
+                    } else {
+                        max_id += 1;
+                        Some(max_id)
+                    }
+                });
+                assignments[idx] = id;
+            } else if let Some(id) = assignments[idx] {
+                // Only frees a room if the interval actually got one; overflowed
+                // (null) intervals must not push a phantom room back onto the heap.
+                free_rooms.push(Reverse(id));
+            }
         }
     }
     Ok(assignments)
 }
 
+/// Resolves the common length of two inputs that may broadcast, mirroring the
+/// broadcasting Polars' own range functions support: equal lengths pass through
+/// unchanged, a length-1 side broadcasts to the other, anything else errors.
+fn broadcast_len(a: usize, b: usize) -> PolarsResult<usize> {
+    match (a, b) {
+        (a, b) if a == b => Ok(a),
+        (1, b) => Ok(b),
+        (a, 1) => Ok(a),
+        (a, b) => Err(PolarsError::ComputeError(
+            format!("Lengths of start ({a}) and end ({b}) must be equal, or one of them must be length 1 to broadcast")
+                .into(),
+        )),
+    }
+}
+
 /// The plugin entry point.
 /// We mark it as 'pub' and ensure it's in the root of this module.
 #[polars_expr(output_type=UInt32)]
 pub fn sweep_line_assignment(inputs: &[Series], kwargs: SweepLineKwargs) -> PolarsResult<Series> {
+    if inputs.len() != 2 && inputs.len() != 3 {
+        return Err(PolarsError::ComputeError(
+            "Required 2 arguments (start, end), plus an optional 3rd partition-key argument"
+                .into(),
+        ));
+    }
+
+    // Broadcast a length-1 side (e.g. a single shared anchor `start`) across the other.
+    let len_start = inputs[0].len();
+    let len_end = inputs[1].len();
+    let n = broadcast_len(len_start, len_end)?;
+
+    let s_start = if len_start == 1 && n != 1 {
+        inputs[0].new_from_index(0, n)
+    } else {
+        inputs[0].clone()
+    };
+    let s_end = if len_end == 1 && n != 1 {
+        inputs[1].new_from_index(0, n)
+    } else {
+        inputs[1].clone()
+    };
+    let s_start = s_start.rechunk();
+    let s_end = s_end.rechunk();
+
+    // An optional partition key scopes the sweep to run independently per group,
+    // so room ids never leak across e.g. venues and are each numbered from 1.
+    let groups = match inputs.get(2) {
+        Some(s_group) => {
+            if s_group.len() != n {
+                return Err(PolarsError::ComputeError(
+                    format!(
+                        "Partition key length ({}) must match start/end length ({n})",
+                        s_group.len()
+                    )
+                    .into(),
+                ));
+            }
+            Some(group_ids_of(s_group)?)
+        },
+        None => None,
+    };
+    let groups = groups.as_deref();
+
+    // Map logical (Datetime/Date) to physical (Int64/Int32)
+    let p_start = s_start.to_physical_repr();
+    let p_end = s_end.to_physical_repr();
+
+    if p_start.dtype() != p_end.dtype() {
+        return Err(PolarsError::ComputeError(
+            "Physical dtypes must match".into(),
+        ));
+    }
+
+    let res = match p_start.dtype() {
+        DataType::Int64 => assign(p_start.i64()?, p_end.i64()?, kwargs.closed, kwargs.max_rooms, groups)?,
+        DataType::Int32 => assign(p_start.i32()?, p_end.i32()?, kwargs.closed, kwargs.max_rooms, groups)?,
+        DataType::Int16 => assign(p_start.i16()?, p_end.i16()?, kwargs.closed, kwargs.max_rooms, groups)?,
+        DataType::Int8 => assign(p_start.i8()?, p_end.i8()?, kwargs.closed, kwargs.max_rooms, groups)?,
+        DataType::UInt64 => assign(p_start.u64()?, p_end.u64()?, kwargs.closed, kwargs.max_rooms, groups)?,
+        DataType::UInt32 => assign(p_start.u32()?, p_end.u32()?, kwargs.closed, kwargs.max_rooms, groups)?,
+        DataType::UInt16 => assign(p_start.u16()?, p_end.u16()?, kwargs.closed, kwargs.max_rooms, groups)?,
+        DataType::UInt8 => assign(p_start.u8()?, p_end.u8()?, kwargs.closed, kwargs.max_rooms, groups)?,
+        _ => {
+            return Err(PolarsError::ComputeError(
+                "Unsupported physical type".into(),
+            ))
+        },
+    };
+
+    let mut ca: UInt32Chunked = res.into_iter().collect();
+    ca.rename(PlSmallStr::from_static("room_id"));
+    Ok(ca.into_series())
+}
+
+/// Companion to `assign`: tracks a running count of active intervals instead of
+/// handing out room ids. Returns the peak count (the minimum number of rooms
+/// `assign` would ever need) plus, per row, the count at that row's own arrival.
+fn concurrency<T>(
+    ca_start: &ChunkedArray<T>,
+    ca_end: &ChunkedArray<T>,
+    closed: ClosedWindow,
+) -> PolarsResult<(u32, Vec<Option<u32>>)>
+where
+    T: PolarsNumericType,
+    T::Native: Ord,
+{
+    let n = ca_start.len();
+    let (events, arrival_type, _departure_type) = build_events(ca_start, ca_end, closed, 0..n)?;
+
+    let mut at_start: Vec<Option<u32>> = vec![None; n];
+    let mut active = 0u32;
+    let mut peak = 0u32;
+
+    for (_, event_type, idx) in events {
+        if event_type == arrival_type {
+            active += 1;
+            peak = peak.max(active);
+            at_start[idx] = Some(active);
+        } else {
+            active -= 1;
+        }
+    }
+    Ok((peak, at_start))
+}
+
+/// The plugin entry point for peak/point-in-time concurrency.
+#[polars_expr(output_type=UInt32)]
+pub fn max_concurrent_overlaps(inputs: &[Series], kwargs: ConcurrencyKwargs) -> PolarsResult<Series> {
+    if inputs.len() != 2 {
+        return Err(PolarsError::ComputeError(
+            "Required 2 arguments (start, end)".into(),
+        ));
+    }
+
+    let s_start = inputs[0].rechunk();
+    let s_end = inputs[1].rechunk();
+
+    let p_start = s_start.to_physical_repr();
+    let p_end = s_end.to_physical_repr();
+
+    if p_start.dtype() != p_end.dtype() {
+        return Err(PolarsError::ComputeError(
+            "Physical dtypes must match".into(),
+        ));
+    }
+
+    let (peak, at_start) = match p_start.dtype() {
+        DataType::Int64 => concurrency(p_start.i64()?, p_end.i64()?, kwargs.closed)?,
+        DataType::Int32 => concurrency(p_start.i32()?, p_end.i32()?, kwargs.closed)?,
+        DataType::Int16 => concurrency(p_start.i16()?, p_end.i16()?, kwargs.closed)?,
+        DataType::Int8 => concurrency(p_start.i8()?, p_end.i8()?, kwargs.closed)?,
+        DataType::UInt64 => concurrency(p_start.u64()?, p_end.u64()?, kwargs.closed)?,
+        DataType::UInt32 => concurrency(p_start.u32()?, p_end.u32()?, kwargs.closed)?,
+        DataType::UInt16 => concurrency(p_start.u16()?, p_end.u16()?, kwargs.closed)?,
+        DataType::UInt8 => concurrency(p_start.u8()?, p_end.u8()?, kwargs.closed)?,
+        _ => {
+            return Err(PolarsError::ComputeError(
+                "Unsupported physical type".into(),
+            ))
+        },
+    };
+
+    if kwargs.per_row {
+        // One UInt32 per row: the concurrency count at that interval's own start.
+        let mut ca: UInt32Chunked = at_start.into_iter().collect();
+        ca.rename(PlSmallStr::from_static("interval_concurrency"));
+        Ok(ca.into_series())
+    } else {
+        // A single scalar; Polars broadcasts a length-1 Series across the input length.
+        let ca = UInt32Chunked::from_slice(PlSmallStr::from_static("max_concurrent_overlaps"), &[peak]);
+        Ok(ca.into_series())
+    }
+}
+
+/// Labels every interval with the id of its transitively-overlapping cluster,
+/// i.e. the connected components of the interval-overlap graph. A new cluster
+/// opens whenever the active count rises from 0 to positive and closes when it
+/// falls back to 0; every arrival processed while a cluster is open joins it.
+fn cluster_ids<T>(
+    ca_start: &ChunkedArray<T>,
+    ca_end: &ChunkedArray<T>,
+    closed: ClosedWindow,
+) -> PolarsResult<Vec<Option<u32>>>
+where
+    T: PolarsNumericType,
+    T::Native: Ord,
+{
+    let n = ca_start.len();
+    let (events, arrival_type, _departure_type) = build_events(ca_start, ca_end, closed, 0..n)?;
+
+    let mut ids: Vec<Option<u32>> = vec![None; n];
+    let mut active = 0u32;
+    let mut next_cluster = 0u32;
+    let mut current_cluster = 0u32;
+
+    for (_, event_type, idx) in events {
+        if event_type == arrival_type {
+            if active == 0 {
+                next_cluster += 1;
+                current_cluster = next_cluster;
+            }
+            active += 1;
+            ids[idx] = Some(current_cluster);
+        } else {
+            active -= 1;
+        }
+    }
+    Ok(ids)
+}
+
+/// The plugin entry point for transitive-overlap cluster labeling.
+#[polars_expr(output_type=UInt32)]
+pub fn overlap_cluster_id(inputs: &[Series], kwargs: ClusterKwargs) -> PolarsResult<Series> {
     if inputs.len() != 2 {
         return Err(PolarsError::ComputeError(
             "Required 2 arguments (start, end)".into(),
@@ -72,7 +407,6 @@ pub fn sweep_line_assignment(inputs: &[Series], kwargs: SweepLineKwargs) -> Pola
     let s_start = inputs[0].rechunk();
     let s_end = inputs[1].rechunk();
 
-    // Map logical (Datetime/Date) to physical (Int64/Int32)
     let p_start = s_start.to_physical_repr();
     let p_end = s_end.to_physical_repr();
 
@@ -83,14 +417,14 @@ pub fn sweep_line_assignment(inputs: &[Series], kwargs: SweepLineKwargs) -> Pola
     }
 
     let res = match p_start.dtype() {
-        DataType::Int64 => assign(p_start.i64()?, p_end.i64()?, kwargs.overlapping)?,
-        DataType::Int32 => assign(p_start.i32()?, p_end.i32()?, kwargs.overlapping)?,
-        DataType::Int16 => assign(p_start.i16()?, p_end.i16()?, kwargs.overlapping)?,
-        DataType::Int8 => assign(p_start.i8()?, p_end.i8()?, kwargs.overlapping)?,
-        DataType::UInt64 => assign(p_start.u64()?, p_end.u64()?, kwargs.overlapping)?,
-        DataType::UInt32 => assign(p_start.u32()?, p_end.u32()?, kwargs.overlapping)?,
-        DataType::UInt16 => assign(p_start.u16()?, p_end.u16()?, kwargs.overlapping)?,
-        DataType::UInt8 => assign(p_start.u8()?, p_end.u8()?, kwargs.overlapping)?,
+        DataType::Int64 => cluster_ids(p_start.i64()?, p_end.i64()?, kwargs.closed)?,
+        DataType::Int32 => cluster_ids(p_start.i32()?, p_end.i32()?, kwargs.closed)?,
+        DataType::Int16 => cluster_ids(p_start.i16()?, p_end.i16()?, kwargs.closed)?,
+        DataType::Int8 => cluster_ids(p_start.i8()?, p_end.i8()?, kwargs.closed)?,
+        DataType::UInt64 => cluster_ids(p_start.u64()?, p_end.u64()?, kwargs.closed)?,
+        DataType::UInt32 => cluster_ids(p_start.u32()?, p_end.u32()?, kwargs.closed)?,
+        DataType::UInt16 => cluster_ids(p_start.u16()?, p_end.u16()?, kwargs.closed)?,
+        DataType::UInt8 => cluster_ids(p_start.u8()?, p_end.u8()?, kwargs.closed)?,
         _ => {
             return Err(PolarsError::ComputeError(
                 "Unsupported physical type".into(),
@@ -98,7 +432,8 @@ pub fn sweep_line_assignment(inputs: &[Series], kwargs: SweepLineKwargs) -> Pola
         },
     };
 
-    let ca = UInt32Chunked::from_vec(PlSmallStr::from_static("room_id"), res);
+    let mut ca: UInt32Chunked = res.into_iter().collect();
+    ca.rename(PlSmallStr::from_static("cluster_id"));
     Ok(ca.into_series())
 }
 
@@ -107,18 +442,26 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_overlap_logic() {
-        // [10, 20] and [20, 30]
+    fn test_closed_window_logic() {
+        // [10, 20] and [20, 30]: the contract is 4-way, one case per `ClosedWindow` variant.
         let start = Int64Chunked::from_slice(PlSmallStr::EMPTY, &[10, 20]);
         let end = Int64Chunked::from_slice(PlSmallStr::EMPTY, &[20, 30]);
 
-        // Non-overlapping: reuse room at tick 20
-        let res_f = assign(&start, &end, false).unwrap();
-        assert_eq!(res_f, vec![1, 1]);
+        // Both closed: touching intervals are treated as overlapping, need separate rooms.
+        let res_both = assign(&start, &end, ClosedWindow::Both, None, None).unwrap();
+        assert_eq!(res_both, vec![Some(1), Some(2)]);
+
+        // Neither closed: touching intervals don't overlap, room is reused.
+        let res_none = assign(&start, &end, ClosedWindow::None, None, None).unwrap();
+        assert_eq!(res_none, vec![Some(1), Some(1)]);
+
+        // Left-closed [s, e): the room is free at the shared endpoint, reused.
+        let res_left = assign(&start, &end, ClosedWindow::Left, None, None).unwrap();
+        assert_eq!(res_left, vec![Some(1), Some(1)]);
 
-        // Overlapping: need new room at tick 20
-        let res_t = assign(&start, &end, true).unwrap();
-        assert_eq!(res_t, vec![1, 2]);
+        // Right-closed (s, e]: the arrival wins the tie, a new room is needed.
+        let res_right = assign(&start, &end, ClosedWindow::Right, None, None).unwrap();
+        assert_eq!(res_right, vec![Some(1), Some(2)]);
     }
 
     #[test]
@@ -141,8 +484,8 @@ mod tests {
         let ca_end = p_end.i64().unwrap();
 
         // 3. Call assign directly
-        let res = assign(ca_start, ca_end, false).unwrap();
-        assert_eq!(res, vec![1]);
+        let res = assign(ca_start, ca_end, ClosedWindow::None, None, None).unwrap();
+        assert_eq!(res, vec![Some(1)]);
     }
 
     #[test]
@@ -156,8 +499,8 @@ mod tests {
         let ca_start = UInt32Chunked::from_slice(PlSmallStr::EMPTY, &starts);
         let ca_end = UInt32Chunked::from_slice(PlSmallStr::EMPTY, &ends);
 
-        // We use overlapping=false to see how well we recycle IDs.
-        let res = assign(&ca_start, &ca_end, false).unwrap();
+        // We use ClosedWindow::None to see how well we recycle IDs.
+        let res = assign(&ca_start, &ca_end, ClosedWindow::None, None, None).unwrap();
 
         assert_eq!(res.len(), 15);
 
@@ -183,4 +526,129 @@ mod tests {
             "Inefficient room allocation detected"
         );
     }
+
+    #[test]
+    fn test_max_rooms_overflow() {
+        // Three intervals all active at once, but only 2 rooms available:
+        // the third arrival must overflow to null.
+        let start = Int64Chunked::from_slice(PlSmallStr::EMPTY, &[1, 2, 3]);
+        let end = Int64Chunked::from_slice(PlSmallStr::EMPTY, &[10, 10, 10]);
+
+        let res = assign(&start, &end, ClosedWindow::None, Some(2), None).unwrap();
+        assert_eq!(res, vec![Some(1), Some(2), None]);
+    }
+
+    #[test]
+    fn test_max_rooms_frees_in_time() {
+        // Room 1 departs at t=5, just before the third interval arrives at t=5,
+        // so with a capacity of 2 it should be reused instead of overflowing.
+        let start = Int64Chunked::from_slice(PlSmallStr::EMPTY, &[1, 2, 5]);
+        let end = Int64Chunked::from_slice(PlSmallStr::EMPTY, &[5, 10, 10]);
+
+        let res = assign(&start, &end, ClosedWindow::None, Some(2), None).unwrap();
+        assert_eq!(res, vec![Some(1), Some(2), Some(1)]);
+    }
+
+    #[test]
+    fn test_concurrency_peak_and_tiebreak() {
+        // [10, 20] and [20, 30]: same touching-intervals case as the room assignment
+        // contract, but here we only care about the peak concurrent count.
+        let start = Int64Chunked::from_slice(PlSmallStr::EMPTY, &[10, 20]);
+        let end = Int64Chunked::from_slice(PlSmallStr::EMPTY, &[20, 30]);
+
+        let (peak_both, _) = concurrency(&start, &end, ClosedWindow::Both).unwrap();
+        assert_eq!(peak_both, 2);
+
+        let (peak_none, _) = concurrency(&start, &end, ClosedWindow::None).unwrap();
+        assert_eq!(peak_none, 1);
+    }
+
+    #[test]
+    fn test_concurrency_per_row_at_start() {
+        // Three intervals overlapping at [5, 10): rows 0 and 1 start before row 2's
+        // arrival, so row 2 should see a concurrency of 3 at its own start.
+        let start = Int64Chunked::from_slice(PlSmallStr::EMPTY, &[1, 2, 5]);
+        let end = Int64Chunked::from_slice(PlSmallStr::EMPTY, &[10, 10, 10]);
+
+        let (peak, at_start) = concurrency(&start, &end, ClosedWindow::None).unwrap();
+        assert_eq!(peak, 3);
+        assert_eq!(at_start, vec![Some(1), Some(2), Some(3)]);
+    }
+
+    #[test]
+    fn test_partitioned_assignment_scopes_room_ids() {
+        // Two overlapping intervals in group 0 and two overlapping intervals in
+        // group 1, interleaved by row. Without partitioning this would need 4
+        // rooms; with partitioning each group should independently need only 2,
+        // each numbered from 1.
+        let start = UInt32Chunked::from_slice(PlSmallStr::EMPTY, &[1, 1, 2, 2]);
+        let end = UInt32Chunked::from_slice(PlSmallStr::EMPTY, &[10, 10, 10, 10]);
+        let groups = [0usize, 1, 0, 1];
+
+        let res = assign(&start, &end, ClosedWindow::None, None, Some(&groups)).unwrap();
+        assert_eq!(res, vec![Some(1), Some(1), Some(2), Some(2)]);
+    }
+
+    #[test]
+    fn test_group_ids_of_hashes_native_values() {
+        // String partition key: groups are assigned in order of first appearance.
+        let venues = Series::new(
+            PlSmallStr::EMPTY,
+            &["a", "b", "a", "c"],
+        );
+        assert_eq!(group_ids_of(&venues).unwrap(), vec![0, 1, 0, 2]);
+
+        // Integer partition key goes through the same physical-hash dispatch.
+        let days = Series::new(PlSmallStr::EMPTY, &[5i64, 5, 7, 5]);
+        assert_eq!(group_ids_of(&days).unwrap(), vec![0, 0, 1, 0]);
+    }
+
+    #[test]
+    fn test_cluster_ids_merges_transitive_overlaps() {
+        // [1,10], [5,15], [20,30]: the first two transitively overlap and merge
+        // into cluster 1, while the third is disjoint and starts cluster 2.
+        let start = Int64Chunked::from_slice(PlSmallStr::EMPTY, &[1, 5, 20]);
+        let end = Int64Chunked::from_slice(PlSmallStr::EMPTY, &[10, 15, 30]);
+
+        let res = cluster_ids(&start, &end, ClosedWindow::None).unwrap();
+        assert_eq!(res, vec![Some(1), Some(1), Some(2)]);
+    }
+
+    #[test]
+    fn test_cluster_ids_respects_closed_tiebreak_and_nulls() {
+        // Touching intervals [1,10] and [10,20]: Both/Right merge them into one
+        // cluster, None/Left split them into two. The null row is skipped entirely.
+        let mut start: Int64Chunked = vec![Some(1i64), None, Some(10)].into_iter().collect();
+        start.rename(PlSmallStr::EMPTY);
+        let mut end: Int64Chunked = vec![Some(10i64), None, Some(20)].into_iter().collect();
+        end.rename(PlSmallStr::EMPTY);
+
+        let res_both = cluster_ids(&start, &end, ClosedWindow::Both).unwrap();
+        assert_eq!(res_both, vec![Some(1), None, Some(1)]);
+
+        let res_none = cluster_ids(&start, &end, ClosedWindow::None).unwrap();
+        assert_eq!(res_none, vec![Some(1), None, Some(2)]);
+
+        let res_left = cluster_ids(&start, &end, ClosedWindow::Left).unwrap();
+        assert_eq!(res_left, vec![Some(1), None, Some(2)]);
+
+        let res_right = cluster_ids(&start, &end, ClosedWindow::Right).unwrap();
+        assert_eq!(res_right, vec![Some(1), None, Some(1)]);
+    }
+
+    #[test]
+    fn test_broadcast_len() {
+        // Equal lengths pass through unchanged.
+        assert_eq!(broadcast_len(3, 3).unwrap(), 3);
+
+        // A length-1 side broadcasts to the other, either direction.
+        assert_eq!(broadcast_len(1, 5).unwrap(), 5);
+        assert_eq!(broadcast_len(5, 1).unwrap(), 5);
+
+        // Two scalars broadcast to length 1.
+        assert_eq!(broadcast_len(1, 1).unwrap(), 1);
+
+        // Anything else is neither equal nor broadcastable.
+        assert!(broadcast_len(2, 3).is_err());
+    }
 }